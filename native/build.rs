@@ -0,0 +1,10 @@
+// Wire up napi-rs type-def emission so the generated `.d.ts` carries the Rust
+// doc comments on every `#[napi]` item through as `/** ... */` JSDoc blocks.
+// Without this setup the type definitions (and the doc comments with them) are
+// never written, which is why `get_version`'s `///` comment used to be dropped
+// on the JS side.
+extern crate napi_build;
+
+fn main() {
+    napi_build::setup();
+}
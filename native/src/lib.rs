@@ -2,6 +2,14 @@
 
 use napi_derive::napi;
 
+mod pty;
+mod runtime;
+mod screen;
+
+pub use pty::Pty;
+pub use runtime::get_napi_version;
+pub use screen::Screen;
+
 /// Returns the native addon version (for sanity check / no-fallback requirement).
 #[napi]
 pub fn get_version() -> String {
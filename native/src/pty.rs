@@ -0,0 +1,313 @@
+//! Hosting a real child process in a pseudo-terminal.
+//!
+//! [`Pty::spawn`] opens a PTY master/slave pair, forks the requested shell onto
+//! the slave, and streams the master's output to JS through a
+//! [`ThreadsafeFunction`]. A dedicated read thread pushes chunks into the
+//! `onData` callback so the Node event loop is never blocked on I/O.
+
+use std::ffi::CString;
+use std::io::Write;
+use std::os::unix::io::{FromRawFd, RawFd};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use napi::bindgen_prelude::Buffer;
+use napi::threadsafe_function::{ThreadsafeFunction, ThreadsafeFunctionCallMode};
+use napi::{Error, Result, Status};
+use napi_derive::napi;
+
+/// Options controlling how the child process is launched.
+#[napi(object)]
+pub struct SpawnOptions {
+    /// Initial terminal width in columns.
+    pub cols: u16,
+    /// Initial terminal height in rows.
+    pub rows: u16,
+    /// Extra environment variables applied on top of the inherited environment,
+    /// as `KEY=VALUE` strings.
+    pub env: Option<Vec<String>>,
+}
+
+/// A child process running inside a pseudo-terminal.
+///
+/// The master file descriptor is owned by Rust; `write`/`resize`/`kill` operate
+/// on it and output is delivered asynchronously to the `onData` callback passed
+/// to [`Pty::spawn`].
+#[napi]
+pub struct Pty {
+    master_fd: RawFd,
+    pid: libc::pid_t,
+    cols: u16,
+    rows: u16,
+    reaped: bool,
+    /// Write end of the self-pipe used to wake the reader out of `poll` so it
+    /// can exit before we close `master_fd`.
+    stop_fd: RawFd,
+    reader: Option<JoinHandle<()>>,
+}
+
+fn set_winsize(fd: RawFd, cols: u16, rows: u16) {
+    let ws = libc::winsize {
+        ws_row: rows,
+        ws_col: cols,
+        ws_xpixel: 0,
+        ws_ypixel: 0,
+    };
+    unsafe {
+        libc::ioctl(fd, libc::TIOCSWINSZ, &ws);
+    }
+}
+
+/// Build the full `KEY=VALUE` environment (inherited, then `overrides` applied)
+/// as owned `CString`s. Done in the parent so the child touches no allocator.
+fn build_envp(overrides: Option<&[String]>) -> Result<Vec<CString>> {
+    use std::collections::BTreeMap;
+
+    let mut map: BTreeMap<String, String> = std::env::vars().collect();
+    if let Some(vars) = overrides {
+        for kv in vars {
+            if let Some((key, value)) = kv.split_once('=') {
+                map.insert(key.to_owned(), value.to_owned());
+            }
+        }
+    }
+    map.into_iter()
+        .map(|(k, v)| {
+            CString::new(format!("{k}={v}"))
+                .map_err(|e| Error::new(Status::InvalidArg, e.to_string()))
+        })
+        .collect()
+}
+
+#[napi]
+impl Pty {
+    /// Spawn `shell` with `args` in a fresh PTY and stream its output.
+    ///
+    /// `on_data` is invoked from a background read thread with each chunk the
+    /// child writes to its terminal; it is never called after the child closes
+    /// the PTY.
+    #[napi(factory)]
+    pub fn spawn(
+        shell: String,
+        args: Vec<String>,
+        options: SpawnOptions,
+        #[napi(ts_arg_type = "(err: null | Error, chunk: Buffer) => void")]
+        on_data: ThreadsafeFunction<Buffer>,
+    ) -> Result<Pty> {
+        // Everything the child needs is allocated here, in the parent, before
+        // the fork: after `forkpty` the child runs alongside Node's other
+        // threads and may only call async-signal-safe functions, so it must not
+        // allocate, take the env lock, or build CStrings itself.
+        let prog = CString::new(shell.as_str())
+            .map_err(|e| Error::new(Status::InvalidArg, e.to_string()))?;
+        let mut argv: Vec<CString> = Vec::with_capacity(args.len() + 1);
+        argv.push(prog.clone());
+        for arg in &args {
+            argv.push(
+                CString::new(arg.as_str())
+                    .map_err(|e| Error::new(Status::InvalidArg, e.to_string()))?,
+            );
+        }
+        let envp = build_envp(options.env.as_deref())?;
+
+        let mut argv_ptr: Vec<*const libc::c_char> = argv.iter().map(|c| c.as_ptr()).collect();
+        argv_ptr.push(std::ptr::null());
+        let mut envp_ptr: Vec<*const libc::c_char> = envp.iter().map(|c| c.as_ptr()).collect();
+        envp_ptr.push(std::ptr::null());
+
+        let ws = libc::winsize {
+            ws_row: options.rows,
+            ws_col: options.cols,
+            ws_xpixel: 0,
+            ws_ypixel: 0,
+        };
+        let mut master_fd: RawFd = -1;
+        // forkpty allocates the master/slave pair, forks, and wires the child's
+        // stdio to the slave in one call — the child returns with pid 0.
+        let pid = unsafe {
+            libc::forkpty(
+                &mut master_fd,
+                std::ptr::null_mut(),
+                std::ptr::null(),
+                &ws,
+            )
+        };
+        if pid < 0 {
+            return Err(Error::new(
+                Status::GenericFailure,
+                "forkpty failed".to_owned(),
+            ));
+        }
+        if pid == 0 {
+            // Child: async-signal-safe only. The CString/pointer buffers were
+            // built in the parent and are visible here via copy-on-write.
+            unsafe {
+                libc::execvpe(prog.as_ptr(), argv_ptr.as_ptr(), envp_ptr.as_ptr());
+                // Only reached if exec failed.
+                libc::_exit(127);
+            }
+        }
+
+        // Self-pipe so Drop can wake the reader out of `poll` and have it exit
+        // cleanly, rather than closing the fd while it is blocked in `read`.
+        let mut pipe_fds = [0 as RawFd; 2];
+        if unsafe { libc::pipe(pipe_fds.as_mut_ptr()) } != 0 {
+            unsafe { libc::close(master_fd) };
+            return Err(Error::new(
+                Status::GenericFailure,
+                "failed to create reader stop pipe".to_owned(),
+            ));
+        }
+        let stop_read = pipe_fds[0];
+        let stop_fd = pipe_fds[1];
+        let reader = spawn_reader(master_fd, stop_read, on_data);
+
+        Ok(Pty {
+            master_fd,
+            pid,
+            cols: options.cols,
+            rows: options.rows,
+            reaped: false,
+            stop_fd,
+            reader: Some(reader),
+        })
+    }
+
+    /// Write `data` to the child's terminal input.
+    #[napi]
+    pub fn write(&mut self, data: Buffer) -> Result<()> {
+        let mut file = unsafe { std::fs::File::from_raw_fd(self.master_fd) };
+        let res = file.write_all(&data);
+        // The PTY outlives this borrowed File; don't close the fd on drop.
+        std::mem::forget(file);
+        res.map_err(|e| Error::new(Status::GenericFailure, e.to_string()))
+    }
+
+    /// Resize the terminal to `cols` × `rows` via `TIOCSWINSZ`.
+    #[napi]
+    pub fn resize(&mut self, cols: u16, rows: u16) {
+        self.cols = cols;
+        self.rows = rows;
+        set_winsize(self.master_fd, cols, rows);
+    }
+
+    /// Send `signal` to the child process (defaults to `SIGTERM`) and reap it if
+    /// it has already exited. The check is a single non-blocking `waitpid`; a
+    /// child that exits after it is reaped when the `Pty` is dropped.
+    #[napi]
+    pub fn kill(&mut self, signal: Option<i32>) {
+        let sig = signal.unwrap_or(libc::SIGTERM);
+        unsafe {
+            libc::kill(self.pid, sig);
+        }
+        self.try_reap();
+    }
+}
+
+impl Pty {
+    /// Collect an already-exited child via `WNOHANG`. Returns `true` once the
+    /// child has been reaped (or is no longer ours).
+    fn try_reap(&mut self) -> bool {
+        if self.reaped {
+            return true;
+        }
+        let mut status = 0;
+        let res = unsafe { libc::waitpid(self.pid, &mut status, libc::WNOHANG) };
+        if res == self.pid || res < 0 {
+            self.reaped = true;
+        }
+        self.reaped
+    }
+
+    /// Terminate and reap the child without ever blocking unboundedly: try a
+    /// polite `WNOHANG` reap, then SIGTERM, then SIGKILL, polling briefly
+    /// between escalations. A child that ignores SIGTERM or is stopped can
+    /// never wedge the finalizer thread.
+    fn terminate_and_reap(&mut self) {
+        if self.try_reap() {
+            return;
+        }
+        for (round, signal) in [libc::SIGTERM, libc::SIGKILL].into_iter().enumerate() {
+            // SIGKILL also dislodges a stopped (SIGSTOP'd) child.
+            unsafe {
+                libc::kill(self.pid, signal);
+            }
+            for _ in 0..10 {
+                if self.try_reap() {
+                    return;
+                }
+                thread::sleep(Duration::from_millis(if round == 0 { 10 } else { 5 }));
+            }
+        }
+    }
+}
+
+impl Drop for Pty {
+    fn drop(&mut self) {
+        // Wake the reader out of `poll` and wait for it to exit *before* closing
+        // the master, so we never close an fd the reader is mid-`read` on.
+        unsafe {
+            libc::close(self.stop_fd);
+        }
+        if let Some(reader) = self.reader.take() {
+            let _ = reader.join();
+        }
+        unsafe {
+            libc::close(self.master_fd);
+        }
+        self.terminate_and_reap();
+    }
+}
+
+fn spawn_reader(
+    master_fd: RawFd,
+    stop_fd: RawFd,
+    on_data: ThreadsafeFunction<Buffer>,
+) -> JoinHandle<()> {
+    thread::spawn(move || {
+        let mut buf = [0u8; 8192];
+        loop {
+            let mut fds = [
+                libc::pollfd {
+                    fd: master_fd,
+                    events: libc::POLLIN,
+                    revents: 0,
+                },
+                libc::pollfd {
+                    fd: stop_fd,
+                    events: libc::POLLIN,
+                    revents: 0,
+                },
+            ];
+            let rc = unsafe { libc::poll(fds.as_mut_ptr(), 2, -1) };
+            if rc < 0 {
+                let err = std::io::Error::last_os_error();
+                if err.kind() == std::io::ErrorKind::Interrupted {
+                    continue;
+                }
+                break;
+            }
+            // Drop signalled us (or the pipe hung up): stop before the owner
+            // closes master_fd.
+            if fds[1].revents & (libc::POLLIN | libc::POLLHUP) != 0 {
+                break;
+            }
+            if fds[0].revents & libc::POLLIN != 0 {
+                let n = unsafe {
+                    libc::read(master_fd, buf.as_mut_ptr() as *mut libc::c_void, buf.len())
+                };
+                if n <= 0 {
+                    break;
+                }
+                let chunk = Buffer::from(&buf[..n as usize]);
+                on_data.call(Ok(chunk), ThreadsafeFunctionCallMode::Blocking);
+            } else if fds[0].revents & (libc::POLLHUP | libc::POLLERR) != 0 {
+                break;
+            }
+        }
+        // The reader owns the read end of the stop pipe; close it on the way out.
+        unsafe {
+            libc::close(stop_fd);
+        }
+    })
+}
@@ -0,0 +1,52 @@
+//! Runtime ABI guard for multi-runtime (Node / Electron) embedding.
+//!
+//! The terminal and framebuffer APIs rely on N-API surface that only exists
+//! from a known baseline. Embedders — notably Electron, whose bundled Node
+//! headers can lag behind vanilla Node — must load the addon against a runtime
+//! at or above that baseline; otherwise the calls would resolve against missing
+//! symbols and crash the renderer process. We check this once at module load
+//! and refuse to register with a clear error rather than failing later.
+
+use napi::{Env, Error, JsObject, Result, Status};
+use napi_derive::{module_exports, napi};
+
+/// Lowest N-API version this addon is built against and will run on.
+#[napi]
+pub const MIN_NAPI_VERSION: u32 = 8;
+
+/// The N-API version reported by the runtime currently hosting the addon.
+///
+/// A JS bootstrap can assert `getNapiVersion() >= MIN_NAPI_VERSION` before
+/// touching the terminal/framebuffer APIs to avoid silent ABI mismatches.
+#[napi]
+pub fn get_napi_version(env: Env) -> Result<u32> {
+    napi_version(&env)
+}
+
+fn napi_version(env: &Env) -> Result<u32> {
+    let mut version: u32 = 0;
+    let status = unsafe { napi::sys::napi_get_version(env.raw(), &mut version) };
+    if status != napi::sys::Status::napi_ok {
+        return Err(Error::new(
+            Status::GenericFailure,
+            "failed to query N-API version".to_owned(),
+        ));
+    }
+    Ok(version)
+}
+
+/// Refuse to register against a runtime below [`MIN_NAPI_VERSION`].
+#[module_exports]
+fn init(_exports: JsObject, env: Env) -> Result<()> {
+    let version = napi_version(&env)?;
+    if version < MIN_NAPI_VERSION {
+        return Err(Error::new(
+            Status::GenericFailure,
+            format!(
+                "react-console requires N-API >= {MIN_NAPI_VERSION}, but the host runtime \
+                 provides N-API {version}; upgrade Node or use an Electron build with matching headers"
+            ),
+        ));
+    }
+    Ok(())
+}
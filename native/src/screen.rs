@@ -0,0 +1,218 @@
+//! The cell framebuffer that React diffs and blits a frame at a time.
+//!
+//! A [`Screen`] owns a contiguous grid of terminal cells in Rust. Each cell is
+//! packed into two `u32` words under a layout that is part of the public API
+//! ([`Screen::pack`]/[`Screen::unpack`] and the `CELL_WORDS`/`BYTES_PER_CELL`
+//! constants) so JS and Rust agree on it without marshalling cell structs one
+//! field at a time. JS reads a frame either as a snapshot via [`Screen::buffer`]
+//! or — in a hot render loop — by handing Rust a pre-allocated buffer to write
+//! into via [`Screen::render_into`], which allocates nothing.
+
+use napi::bindgen_prelude::{Uint32Array, Uint8Array};
+use napi::{Env, Result};
+use napi_derive::napi;
+
+/// Number of `u32` words used to encode a single cell.
+///
+/// A cell does not fit in one word: the 21-bit codepoint alone leaves no room
+/// for the colour indices and attribute bits, so cells span two words (the low
+/// word carries the glyph and foreground, the high word the background in its
+/// low 8 bits and the attributes above it).
+#[napi]
+pub const CELL_WORDS: u32 = 2;
+
+/// Bytes occupied by a single packed cell (`CELL_WORDS * 4`).
+#[napi]
+pub const BYTES_PER_CELL: u32 = CELL_WORDS * 4;
+
+const CODEPOINT_BITS: u32 = 21;
+const COLOR_BITS: u32 = 8;
+
+const ATTR_BITS: u32 = 32 - COLOR_BITS;
+
+const CODEPOINT_MASK: u32 = (1 << CODEPOINT_BITS) - 1;
+const COLOR_MASK: u32 = (1 << COLOR_BITS) - 1;
+const ATTR_MASK: u32 = (1 << ATTR_BITS) - 1;
+
+const FG_SHIFT: u32 = CODEPOINT_BITS;
+const ATTR_SHIFT: u32 = COLOR_BITS;
+
+/// A decoded cell: the glyph plus its colours and attributes.
+///
+/// This is the object form of the two-word packing returned by
+/// [`Screen::unpack`]; [`Screen::pack`] turns it back into `[lo, hi]`.
+#[napi(object)]
+pub struct Cell {
+    /// Unicode scalar value of the glyph (21 bits).
+    pub codepoint: u32,
+    /// 256-colour foreground palette index.
+    pub fg: u8,
+    /// 256-colour background palette index.
+    pub bg: u8,
+    /// Attribute bitset (bold, underline, …), 24 bits, carried in the high word
+    /// above the background index.
+    pub attrs: u32,
+}
+
+fn pack_words(codepoint: u32, fg: u8, bg: u8, attrs: u32) -> (u32, u32) {
+    let lo = (codepoint & CODEPOINT_MASK) | ((fg as u32 & COLOR_MASK) << FG_SHIFT);
+    let hi = (bg as u32 & COLOR_MASK) | ((attrs & ATTR_MASK) << ATTR_SHIFT);
+    (lo, hi)
+}
+
+fn unpack_words(lo: u32, hi: u32) -> Cell {
+    Cell {
+        codepoint: lo & CODEPOINT_MASK,
+        fg: ((lo >> FG_SHIFT) & COLOR_MASK) as u8,
+        bg: (hi & COLOR_MASK) as u8,
+        attrs: (hi >> ATTR_SHIFT) & ATTR_MASK,
+    }
+}
+
+/// A grid of terminal cells backed by a single contiguous `Vec<u32>`.
+///
+/// The allocation is `cols * rows * CELL_WORDS` words laid out row-major. Rust
+/// owns the grid and mutates it in place on [`Screen::set_cell`] and
+/// [`Screen::resize`]; JS obtains a frame through [`Screen::buffer`] or
+/// [`Screen::render_into`].
+#[napi]
+pub struct Screen {
+    cols: u32,
+    rows: u32,
+    cells: Vec<u32>,
+}
+
+#[napi]
+impl Screen {
+    /// Allocate a blank `cols` × `rows` screen.
+    #[napi(constructor)]
+    pub fn new(cols: u32, rows: u32) -> Self {
+        Screen {
+            cols,
+            rows,
+            cells: vec![0; cell_len(cols, rows)],
+        }
+    }
+
+    /// Pack a glyph and its colours/attributes into the two-word cell encoding,
+    /// returned as `[lo, hi]` ready to pass to [`Screen::set_cell`].
+    #[napi]
+    pub fn pack(codepoint: u32, fg: u8, bg: u8, attrs: u32) -> Uint32Array {
+        let (lo, hi) = pack_words(codepoint, fg, bg, attrs);
+        Uint32Array::new(vec![lo, hi])
+    }
+
+    /// Decode a cell's two words back into its glyph, colours and attributes.
+    #[napi]
+    pub fn unpack(lo: u32, hi: u32) -> Cell {
+        unpack_words(lo, hi)
+    }
+
+    /// Width of the grid in cells.
+    #[napi(getter)]
+    pub fn cols(&self) -> u32 {
+        self.cols
+    }
+
+    /// Height of the grid in cells.
+    #[napi(getter)]
+    pub fn rows(&self) -> u32 {
+        self.rows
+    }
+
+    /// A snapshot copy of the packed cell buffer.
+    ///
+    /// NOTE: this is **not** a zero-copy view. It allocates a fresh
+    /// `Uint32Array` and copies all `cols * rows * CELL_WORDS` words out of the
+    /// Rust grid on every call, and the result is detached — later mutations are
+    /// not reflected. It is a convenience for one-off reads and inspection.
+    ///
+    /// For a hot render loop, [`Screen::render_into`] is the only allocation-free
+    /// path: hand it a `Uint8Array` you already own and Rust writes the frame in
+    /// place, copying nothing into a new allocation.
+    #[napi]
+    pub fn buffer(&self) -> Uint32Array {
+        Uint32Array::new(self.cells.clone())
+    }
+
+    /// Re-lay-out the grid to `cols` × `rows`, preserving overlapping cells.
+    ///
+    /// Resizing allocates a new grid and copies the overlapping cells into it;
+    /// the old allocation is not reused. Cells outside the old grid are cleared
+    /// and cells beyond the new bounds are dropped.
+    #[napi]
+    pub fn resize(&mut self, cols: u32, rows: u32) {
+        let mut next = vec![0u32; cell_len(cols, rows)];
+        let copy_cols = cols.min(self.cols);
+        let copy_rows = rows.min(self.rows);
+        let words = CELL_WORDS as usize;
+        for y in 0..copy_rows {
+            for x in 0..copy_cols {
+                let src = cell_offset(self.cols, x, y);
+                let dst = cell_offset(cols, x, y);
+                next[dst..dst + words].copy_from_slice(&self.cells[src..src + words]);
+            }
+        }
+        self.cols = cols;
+        self.rows = rows;
+        self.cells = next;
+    }
+
+    /// Write a packed cell at `(x, y)`.
+    ///
+    /// `lo`/`hi` are the two words produced by [`Screen::pack`]. Out-of-range
+    /// coordinates are ignored so a stray write never corrupts the grid.
+    #[napi]
+    pub fn set_cell(&mut self, x: u32, y: u32, lo: u32, hi: u32) {
+        if x >= self.cols || y >= self.rows {
+            return;
+        }
+        let base = cell_offset(self.cols, x, y);
+        self.cells[base] = lo;
+        self.cells[base + 1] = hi;
+    }
+
+    /// Render the current frame directly into a JS-owned byte buffer.
+    ///
+    /// `target` must be at least `cols * rows * BYTES_PER_CELL` bytes; each cell
+    /// is written little-endian as its two packed words. An undersized buffer
+    /// yields a JS `RangeError` — the length check is the memory-safety
+    /// boundary, so it is enforced before any write.
+    ///
+    /// The borrow is valid only for the duration of this synchronous call; Rust
+    /// never retains the pointer past return.
+    #[napi]
+    pub fn render_into(&self, env: Env, target: &mut Uint8Array) -> Result<()> {
+        let expected = self.cells.len() * 4;
+        if target.len() < expected {
+            env.throw_range_error(
+                format!(
+                    "render_into target is {} bytes but this {}x{} screen needs {}",
+                    target.len(),
+                    self.cols,
+                    self.rows,
+                    expected
+                ),
+                None,
+            )?;
+            return Ok(());
+        }
+        let bytes: &mut [u8] = target.as_mut();
+        for (i, word) in self.cells.iter().enumerate() {
+            let off = i * 4;
+            bytes[off..off + 4].copy_from_slice(&word.to_le_bytes());
+        }
+        Ok(())
+    }
+}
+
+/// Number of `u32` words backing a `cols` × `rows` grid, computed in `usize` so
+/// large dimensions can't overflow into a too-small allocation.
+fn cell_len(cols: u32, rows: u32) -> usize {
+    cols as usize * rows as usize * CELL_WORDS as usize
+}
+
+/// Word offset of cell `(x, y)` in a grid `cols` wide.
+fn cell_offset(cols: u32, x: u32, y: u32) -> usize {
+    (y as usize * cols as usize + x as usize) * CELL_WORDS as usize
+}